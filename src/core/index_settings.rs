@@ -0,0 +1,32 @@
+/// Index-wide settings persisted alongside the index meta.
+///
+/// These are decided once, when the index is created, and apply to
+/// every segment written into it afterwards.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct IndexSettings {
+    /// If set, documents are written to segments ordered by this
+    /// field, letting merges use an efficient k-way merge that
+    /// preserves the order, and range queries short-circuit once
+    /// they run past the requested range.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_by_field: Option<IndexSortByField>,
+}
+
+/// A field and direction that segments are sorted by.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IndexSortByField {
+    /// Name of the field to sort by.
+    pub field: String,
+    /// Sort direction.
+    pub order: Order,
+}
+
+/// Sort direction for `IndexSortByField`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Order {
+    /// Smallest values first.
+    Asc,
+    /// Largest values first.
+    Desc,
+}
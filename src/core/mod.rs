@@ -0,0 +1,10 @@
+pub mod index_settings;
+mod segment_component;
+mod segment_meta;
+mod term_metadata_fetcher;
+
+pub use self::segment_component::SegmentComponent;
+pub use self::segment_meta::SegmentMeta;
+pub use self::term_metadata_fetcher::{
+    SegmentTermMetadataFetcher, TermMetadata, TermMetadataFetcher,
+};
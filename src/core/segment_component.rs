@@ -0,0 +1,54 @@
+use std::slice;
+
+/// Enum describing each component of a tantivy segment.
+///
+/// Each component is stored in its own file,
+/// using the segment id as a prefix.
+#[derive(Eq, PartialEq, Debug, Copy, Clone, Hash)]
+pub enum SegmentComponent {
+    /// Postings (or inverted list). Sorted lists of document ids, associated
+    /// to terms.
+    POSTINGS,
+    /// Positions of terms in each document.
+    POSITIONS,
+    /// Column-oriented random-access storage of fields.
+    FASTFIELDS,
+    /// Stores the sum of the length (in terms) of each field for each document.
+    /// Field norms are stored as a fast field.
+    FIELDNORMS,
+    /// Dictionary associating `Term`s to `TermInfo`s which is
+    /// simply an address into the `postings` file and the `positions` file.
+    TERMS,
+    /// Row-oriented, compressed storage of the documents.
+    /// Accessing a document is requires decompressing a whole block.
+    STORE,
+    /// Bitset describing which documents of the segment are alive.
+    /// Legacy files hold the deleted documents instead.
+    DELETE,
+    /// Append-only staging area for documents that have not yet been
+    /// compressed into the final `STORE`. Never reported by
+    /// `SegmentComponent::iterator()`: it is reclaimed by the garbage
+    /// collector as soon as the real `STORE` has been written, and
+    /// should never be treated as a persistent segment file.
+    TEMPSTORE,
+}
+
+impl SegmentComponent {
+    /// Iterates through the components that make up a persisted,
+    /// searchable segment.
+    ///
+    /// This deliberately excludes `TEMPSTORE`, which is a transient
+    /// staging file and not part of a segment's durable footprint.
+    pub fn iterator() -> slice::Iter<'static, SegmentComponent> {
+        static SEGMENT_COMPONENTS: [SegmentComponent; 7] = [
+            SegmentComponent::POSTINGS,
+            SegmentComponent::POSITIONS,
+            SegmentComponent::FASTFIELDS,
+            SegmentComponent::FIELDNORMS,
+            SegmentComponent::TERMS,
+            SegmentComponent::STORE,
+            SegmentComponent::DELETE,
+        ];
+        SEGMENT_COMPONENTS.iter()
+    }
+}
@@ -1,7 +1,8 @@
 use super::SegmentComponent;
+use core::index_settings::IndexSortByField;
 use core::SegmentId;
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use census::{TrackedObject, Inventory};
 use std::fmt;
 use serde;
@@ -12,6 +13,24 @@ lazy_static! {
     };
 }
 
+/// Filenames that belong to the index as a whole rather than to any
+/// individual segment. No `SegmentMeta` ever lists these, so they must
+/// be excluded explicitly when classifying a raw directory listing,
+/// or they would be mistaken for orphaned segment files.
+const INDEX_LEVEL_FILENAMES: [&str; 4] = [
+    "meta.json",
+    ".managed.json",
+    ".tantivy-writer.lock",
+    ".tantivy-meta.lock",
+];
+
+fn is_index_level_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| INDEX_LEVEL_FILENAMES.contains(&name))
+        .unwrap_or(false)
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct DeleteMeta {
     num_deleted_docs: u32,
@@ -56,6 +75,35 @@ impl SegmentMeta {
         INVENTORY.list().into_iter().map(|inner| SegmentMeta {inner}).collect::<Vec<_>>()
     }
 
+    /// Given a directory listing, returns the subset of files that
+    /// belong to no live `SegmentMeta` and can therefore be safely
+    /// garbage collected.
+    ///
+    /// `list_files()` on a single `SegmentMeta` only reports the
+    /// delete generation it currently holds, so a segment that has
+    /// been deleted from several times over leaves older
+    /// `<uuid>.<opstamp>.del` files behind. Computing the live set
+    /// from every `SegmentMeta` snapshot still tracked by the
+    /// `INVENTORY` census -- rather than just the latest meta for
+    /// each segment -- means a reader that is still holding on to an
+    /// older opstamp keeps its delete file out of the orphan set.
+    ///
+    /// `existing_files` is expected to be a raw directory listing, so
+    /// it also contains files no `SegmentMeta` ever lists: the index's
+    /// own `meta.json`, the managed-files list, and lock files. Those
+    /// are filtered out here rather than being reported as orphans.
+    pub fn list_orphan_files(existing_files: &HashSet<PathBuf>) -> HashSet<PathBuf> {
+        let live_files: HashSet<PathBuf> = SegmentMeta::all()
+            .iter()
+            .flat_map(SegmentMeta::list_files)
+            .collect();
+        existing_files
+            .difference(&live_files)
+            .filter(|path| !is_index_level_file(*path))
+            .cloned()
+            .collect()
+    }
+
     /// Creates a new segment meta for
     /// a segment with no deletes and no documents.
     pub fn new(segment_id: SegmentId) -> SegmentMeta {
@@ -106,6 +154,7 @@ impl SegmentMeta {
             SegmentComponent::FASTFIELDS => ".fast".to_string(),
             SegmentComponent::FIELDNORMS => ".fieldnorm".to_string(),
             SegmentComponent::DELETE => format!(".{}.del", self.delete_opstamp().unwrap_or(0)),
+            SegmentComponent::TEMPSTORE => ".store.tmp".to_string(),
         });
         PathBuf::from(path)
     }
@@ -121,6 +170,17 @@ impl SegmentMeta {
 
     /// Return the number of documents in the segment.
     pub fn num_docs(&self) -> u32 {
+        self.num_alive_docs()
+    }
+
+    /// Returns the number of alive (non-deleted) documents in the
+    /// segment.
+    ///
+    /// This mirrors the alive set that `DeleteBitSet` stores in the
+    /// `.del` file. It is computed as `max_doc` minus
+    /// `num_deleted_docs()`, which reads the deleted count recorded in
+    /// the segment's delete meta.
+    pub fn num_alive_docs(&self) -> u32 {
         self.max_doc() - self.num_deleted_docs()
     }
 
@@ -139,6 +199,16 @@ impl SegmentMeta {
         self.num_deleted_docs() > 0
     }
 
+    /// Returns the field and direction the segment's documents are
+    /// sorted by, if the index was configured with a sort order when
+    /// this segment was written.
+    ///
+    /// Legacy segments, written before index sorting existed,
+    /// deserialize with `sort = None`.
+    pub fn sort_by_field(&self) -> Option<IndexSortByField> {
+        self.inner.sort.clone()
+    }
+
     #[doc(hidden)]
     pub fn with_max_doc(self, max_doc: u32) -> SegmentMeta {
         let tracked = self.inner
@@ -148,6 +218,7 @@ impl SegmentMeta {
                     segment_id: inner_meta_clone.segment_id,
                     max_doc,
                     deletes: inner_meta_clone.deletes,
+                    sort: inner_meta_clone.sort,
                 }
             });
         SegmentMeta {
@@ -167,6 +238,23 @@ impl SegmentMeta {
                     segment_id: inner_meta.segment_id,
                     max_doc: inner_meta.max_doc,
                     deletes: Some(delete_meta),
+                    sort: inner_meta.sort.clone(),
+                }
+            });
+        SegmentMeta {
+            inner: tracked
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn with_sort(self, sort: Option<IndexSortByField>) -> SegmentMeta {
+        let tracked = self.inner
+            .map(move |inner_meta| {
+                InnerSegmentMeta {
+                    segment_id: inner_meta.segment_id,
+                    max_doc: inner_meta.max_doc,
+                    deletes: inner_meta.deletes.clone(),
+                    sort: sort.clone(),
                 }
             });
         SegmentMeta {
@@ -180,6 +268,8 @@ struct InnerSegmentMeta {
     segment_id: SegmentId,
     max_doc: u32,
     deletes: Option<DeleteMeta>,
+    #[serde(default)]
+    sort: Option<IndexSortByField>,
 }
 
 impl InnerSegmentMeta {
@@ -188,6 +278,7 @@ impl InnerSegmentMeta {
             segment_id,
             max_doc: 0,
             deletes: None,
+            sort: None,
         }
     }
 }
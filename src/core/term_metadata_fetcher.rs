@@ -0,0 +1,168 @@
+use std::io;
+
+use core::SegmentMeta;
+use super::SegmentComponent;
+use directory::{Directory, ReadOnlySource};
+use fastfield::DeleteBitSet;
+use postings::{BlockSegmentPostings, DocSet, SegmentPostings};
+use schema::Term;
+use termdict::{TermDictionary, TermInfo};
+
+/// Per-term metadata resolved directly from a segment's on-disk
+/// dictionary, postings and delete information.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TermMetadata {
+    /// The term ordinal within the segment's term dictionary, if the
+    /// term is present.
+    pub term_ord: Option<u64>,
+    /// The number of documents in the segment carrying the term,
+    /// including documents that have since been deleted.
+    pub doc_freq: u32,
+    /// The number of those documents that are deleted according to
+    /// the segment's delete information.
+    pub num_deleted_docs: u32,
+}
+
+impl TermMetadata {
+    fn absent() -> TermMetadata {
+        TermMetadata {
+            term_ord: None,
+            doc_freq: 0,
+            num_deleted_docs: 0,
+        }
+    }
+
+    /// Returns true iff every document carrying the term has been
+    /// deleted, i.e. the term is effectively gone from the segment.
+    pub fn is_dead(&self) -> bool {
+        self.num_deleted_docs == self.doc_freq
+    }
+}
+
+/// Resolves per-term metadata for a batch of terms against a single
+/// segment, in one forward pass over its `TermDictionary` that stops at
+/// the last requested term, rather than one dictionary lookup per term.
+/// Postings are read only for terms present in the dictionary, and only
+/// when the segment has deletes.
+///
+/// This is meant for callers -- typically a merge planner, or a remote
+/// process that has only downloaded a segment's small files -- that
+/// need to know whether a term is effectively gone from a segment
+/// before committing to the cost of rewriting its postings.
+pub trait TermMetadataFetcher {
+    /// Fetches metadata for `terms`, in the same order they were given.
+    ///
+    /// A term absent from the segment's dictionary yields a
+    /// `TermMetadata` with `doc_freq == 0` and `num_deleted_docs == 0`.
+    fn fetch_batch(
+        &self,
+        segment_meta: &SegmentMeta,
+        terms: &[Term],
+    ) -> io::Result<Vec<TermMetadata>>;
+}
+
+/// Default `TermMetadataFetcher`, backed by a `Directory` holding the
+/// segment's `.term`, `.idx` and `.del` files.
+pub struct SegmentTermMetadataFetcher<D: Directory> {
+    directory: D,
+}
+
+impl<D: Directory> SegmentTermMetadataFetcher<D> {
+    /// Creates a new fetcher reading segment files out of `directory`.
+    pub fn new(directory: D) -> SegmentTermMetadataFetcher<D> {
+        SegmentTermMetadataFetcher { directory }
+    }
+
+    fn delete_bitset(&self, segment_meta: &SegmentMeta) -> io::Result<Option<DeleteBitSet>> {
+        if !segment_meta.has_deletes() {
+            return Ok(None);
+        }
+        let del_path = segment_meta.relative_path(SegmentComponent::DELETE);
+        let source = self.directory.open_read(&del_path)?;
+        Ok(Some(DeleteBitSet::open(source, segment_meta.max_doc())?))
+    }
+
+    /// Counts deletions for a term already located in the dictionary,
+    /// given the ordinal the dictionary resolved it to.
+    fn term_metadata(
+        &self,
+        postings_source: &ReadOnlySource,
+        term_info: &TermInfo,
+        term_ord: u64,
+        delete_bitset: &Option<DeleteBitSet>,
+    ) -> io::Result<TermMetadata> {
+        let doc_freq = term_info.doc_freq;
+        let num_deleted_docs = match delete_bitset {
+            Some(bitset) => {
+                let postings_slice =
+                    postings_source.slice_from(term_info.postings_offset as usize);
+                let block_postings = BlockSegmentPostings::open(doc_freq, postings_slice)?;
+                let mut postings = SegmentPostings::from_block_postings(block_postings, None);
+                let mut num_deleted_docs = 0u32;
+                while postings.advance() {
+                    // Postings only ever enumerate doc ids local to
+                    // this segment, so they can be tested against the
+                    // segment's own delete bitset directly.
+                    if !bitset.is_alive(postings.doc()) {
+                        num_deleted_docs += 1;
+                    }
+                }
+                num_deleted_docs
+            }
+            None => 0,
+        };
+        Ok(TermMetadata {
+            term_ord: Some(term_ord),
+            doc_freq,
+            num_deleted_docs,
+        })
+    }
+}
+
+impl<D: Directory> TermMetadataFetcher for SegmentTermMetadataFetcher<D> {
+    fn fetch_batch(
+        &self,
+        segment_meta: &SegmentMeta,
+        terms: &[Term],
+    ) -> io::Result<Vec<TermMetadata>> {
+        let term_path = segment_meta.relative_path(SegmentComponent::TERMS);
+        let term_source = self.directory.open_read(&term_path)?;
+        let term_dictionary = TermDictionary::open(term_source)?;
+        let delete_bitset = self.delete_bitset(segment_meta)?;
+
+        let postings_path = segment_meta.relative_path(SegmentComponent::POSTINGS);
+        let postings_source = self.directory.open_read(&postings_path)?;
+
+        // Terms are visited in dictionary order so the `.term` file is
+        // walked forward-only in a single pass -- one stream, advanced
+        // term-by-term -- instead of re-seeking from the FST root for
+        // every lookup.
+        let mut sorted_indices: Vec<usize> = (0..terms.len()).collect();
+        sorted_indices.sort_by(|&a, &b| terms[a].value_bytes().cmp(terms[b].value_bytes()));
+
+        let mut results = vec![TermMetadata::absent(); terms.len()];
+        let mut stream = term_dictionary.stream();
+        let mut current = stream.next();
+        for index in sorted_indices {
+            let target = terms[index].value_bytes();
+            while let Some((key, _)) = current {
+                if key < target {
+                    current = stream.next();
+                } else {
+                    break;
+                }
+            }
+            if let Some((key, term_info)) = current {
+                if key == target {
+                    results[index] = self.term_metadata(
+                        &postings_source,
+                        term_info,
+                        stream.term_ord(),
+                        &delete_bitset,
+                    )?;
+                }
+            }
+        }
+        Ok(results)
+    }
+}
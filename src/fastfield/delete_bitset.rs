@@ -0,0 +1,105 @@
+use std::io;
+
+use common::BitSet;
+use directory::ReadOnlySource;
+use DocId;
+
+/// First byte of every marker-prefixed `.del` file, identifying how the
+/// rest of the file should be interpreted.
+///
+/// `FORMAT_ALIVE_DOCS` is the only value ever written: legacy files
+/// predating this marker carry no such byte at all and are told apart
+/// by length in `DeleteBitSet::open`, not by sniffing a marker they
+/// never had.
+const FORMAT_ALIVE_DOCS: u8 = 1;
+
+/// A dense bitset over `0..max_doc`, recording which documents of a
+/// segment are still alive.
+///
+/// Storing the alive set rather than the deleted set makes the common
+/// read path -- checking whether a given doc id is a match during
+/// search -- a direct membership test instead of a negation, and makes
+/// merging two segments a plain bitwise AND of their alive sets.
+pub struct DeleteBitSet {
+    alive_bitset: BitSet,
+}
+
+impl DeleteBitSet {
+    /// Creates a `DeleteBitSet` with all `max_doc` documents alive.
+    pub fn new(max_doc: u32) -> DeleteBitSet {
+        let mut alive_bitset = BitSet::with_max_value(max_doc);
+        for doc in 0..max_doc {
+            alive_bitset.insert(doc);
+        }
+        DeleteBitSet { alive_bitset }
+    }
+
+    /// Opens a `.del` file, transparently upgrading the legacy
+    /// deleted-doc format to the alive-doc representation used in
+    /// memory.
+    ///
+    /// Legacy files predate the format marker byte entirely: they are
+    /// a bare `BitSet::serialize` of the deleted docs, with nothing
+    /// prepended. There is therefore no byte in the file itself to
+    /// sniff -- `max_doc` (taken from the owning segment's meta) is
+    /// what lets us tell the two formats apart, by comparing the
+    /// file's length against a bare serialization for that `max_doc`.
+    pub fn open(source: ReadOnlySource, max_doc: u32) -> io::Result<DeleteBitSet> {
+        let data = source.as_slice();
+        let mut legacy_len_buf = Vec::new();
+        BitSet::with_max_value(max_doc).serialize(&mut legacy_len_buf)?;
+        let alive_bitset = if data.len() == legacy_len_buf.len() {
+            BitSet::deserialize(data)?.negated()
+        } else {
+            let (format, payload) = data.split_first().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::UnexpectedEof, "empty .del file")
+            })?;
+            let bitset = BitSet::deserialize(payload)?;
+            match *format {
+                FORMAT_ALIVE_DOCS => bitset,
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "unknown .del file format marker",
+                    ))
+                }
+            }
+        };
+        Ok(DeleteBitSet { alive_bitset })
+    }
+
+    /// Serializes this bitset, always using the current (alive-doc)
+    /// format.
+    pub fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&[FORMAT_ALIVE_DOCS])?;
+        self.alive_bitset.serialize(writer)
+    }
+
+    /// Marks `doc` as deleted.
+    pub fn delete(&mut self, doc: DocId) {
+        self.alive_bitset.remove(doc);
+    }
+
+    /// Returns true iff `doc` is alive.
+    pub fn is_alive(&self, doc: DocId) -> bool {
+        self.alive_bitset.contains(doc)
+    }
+
+    /// Returns true iff `doc` has been deleted.
+    pub fn is_deleted(&self, doc: DocId) -> bool {
+        !self.is_alive(doc)
+    }
+
+    /// Returns the number of alive documents.
+    pub fn num_alive_docs(&self) -> u32 {
+        self.alive_bitset.len() as u32
+    }
+
+    /// Intersects this alive set with `other`'s, in place.
+    ///
+    /// This is the operation used when merging segments: a document
+    /// survives the merge only if it was alive on both sides.
+    pub fn intersect(&mut self, other: &DeleteBitSet) {
+        self.alive_bitset.intersect_update(&other.alive_bitset);
+    }
+}
@@ -0,0 +1,3 @@
+mod delete_bitset;
+
+pub use self::delete_bitset::DeleteBitSet;